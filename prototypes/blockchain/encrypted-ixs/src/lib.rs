@@ -4,6 +4,25 @@ use arcis::*;
 mod circuits {
     use arcis::*;
 
+    const CARD_COUNT: usize = 16;
+
+    // Fixed Batcher odd-even mergesort comparator network for 16 elements.
+    // The sequence of indices is public (it never depends on secret data),
+    // only the compare-exchange outcome at each step is secret.
+    const SHUFFLE_NETWORK: [(usize, usize); 63] = [
+        (0, 1), (2, 3), (0, 2), (1, 3), (1, 2),
+        (4, 5), (6, 7), (4, 6), (5, 7), (5, 6),
+        (0, 4), (2, 6), (2, 4), (1, 5), (3, 7), (3, 5), (1, 2), (3, 4), (5, 6),
+        (8, 9), (10, 11), (8, 10), (9, 11), (9, 10),
+        (12, 13), (14, 15), (12, 14), (13, 15), (13, 14),
+        (8, 12), (10, 14), (10, 12), (9, 13), (11, 15), (11, 13), (9, 10), (11, 12), (13, 14),
+        (0, 8), (4, 12), (4, 8), (2, 10), (6, 14), (6, 10), (2, 4), (6, 8), (10, 12),
+        (1, 9), (5, 13), (5, 9), (3, 11), (7, 15), (7, 11), (3, 5), (7, 9), (11, 13),
+        (1, 2), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12), (13, 14),
+    ];
+
+    const MAX_PAIR_BATCH: usize = 8;
+
     pub struct VerifyPairInput {
         card_a: u8,
         card_b: u8,
@@ -15,4 +34,129 @@ mod circuits {
         let is_match: u8 = if input.card_a == input.card_b { 1 } else { 0 };
         input_ctxt.owner.from_arcis(is_match)
     }
+
+    pub struct VerifyPairsInput {
+        card_a: [u8; MAX_PAIR_BATCH],
+        card_b: [u8; MAX_PAIR_BATCH],
+    }
+
+    #[instruction]
+    pub fn verify_pairs(input_ctxt: Enc<Shared, VerifyPairsInput>) -> Enc<Shared, [u8; MAX_PAIR_BATCH]> {
+        let input = input_ctxt.to_arcis();
+        let mut is_match = [0u8; MAX_PAIR_BATCH];
+        for i in 0..MAX_PAIR_BATCH {
+            is_match[i] = if input.card_a[i] == input.card_b[i] { 1 } else { 0 };
+        }
+        input_ctxt.owner.from_arcis(is_match)
+    }
+
+    pub struct ShuffleDeckInput {
+        cards: [u8; CARD_COUNT],
+        board_nonce: u128,
+        round_id: u128,
+    }
+
+    // Data-oblivious conditional swap: data movement is independent of the
+    // secret comparison outcome, only arithmetic blending picks the winner.
+    fn compare_exchange(key_a: u32, key_b: u32, card_a: u8, card_b: u8) -> (u32, u32, u8, u8) {
+        let lt: u32 = if key_a < key_b { 1 } else { 0 };
+        let ge: u32 = 1 - lt;
+        let new_key_a = lt * key_a + ge * key_b;
+        let new_key_b = lt * key_b + ge * key_a;
+
+        let lt8 = lt as u8;
+        let ge8 = ge as u8;
+        let new_card_a = lt8 * card_a + ge8 * card_b;
+        let new_card_b = lt8 * card_b + ge8 * card_a;
+
+        (new_key_a, new_key_b, new_card_a, new_card_b)
+    }
+
+    #[instruction]
+    pub fn shuffle_deck(input_ctxt: Enc<Shared, ShuffleDeckInput>) -> Enc<Shared, [u8; CARD_COUNT]> {
+        let input = input_ctxt.to_arcis();
+        let mut cards = input.cards;
+
+        // Per-card sort key comes from a PRF the cluster evaluates over its own
+        // secret-shared key material, keyed on `(board_nonce, round_id)` —
+        // both public (stored plaintext in round_state, passed in here via
+        // plaintext_u128) but neither determines the key schedule alone:
+        // nobody outside the MXE cluster holds the other half of the PRF, so
+        // the permutation stays unpredictable to both the client and the
+        // operator. Folding `round_id` in (not just `board_nonce`) closes the
+        // nonce-reuse case: a player who replays a `board_nonce` across two
+        // different rounds still gets two unrelated shuffles. Keying on
+        // cluster-visible round data rather than drawing fresh randomness per
+        // call is what lets two independent `deal_board` calls (one per
+        // player, same round_id and registered board_nonce) converge on the
+        // identical shuffle, matching `Match.board_commitment`.
+        let entropy: u128 = ArcisRNG::seeded_u128(input.board_nonce, input.round_id);
+        let mut keys = [0u32; CARD_COUNT];
+        for i in 0..CARD_COUNT {
+            keys[i] = hash(i as u64, entropy) as u32;
+        }
+
+        for &(i, j) in SHUFFLE_NETWORK.iter() {
+            let (new_key_i, new_key_j, new_card_i, new_card_j) =
+                compare_exchange(keys[i], keys[j], cards[i], cards[j]);
+            keys[i] = new_key_i;
+            keys[j] = new_key_j;
+            cards[i] = new_card_i;
+            cards[j] = new_card_j;
+        }
+
+        input_ctxt.owner.from_arcis(cards)
+    }
+
+    pub struct VerifySolutionInput {
+        cards_a: [u8; CARD_COUNT],
+        cards_b: [u8; CARD_COUNT],
+        claimed_a_idx: [u8; CARD_COUNT / 2],
+        claimed_b_idx: [u8; CARD_COUNT / 2],
+    }
+
+    fn compare_exchange_u8(a: u8, b: u8) -> (u8, u8) {
+        let lt: u8 = if a < b { 1 } else { 0 };
+        let ge: u8 = 1 - lt;
+        let lo = lt * a + ge * b;
+        let hi = lt * b + ge * a;
+        (lo, hi)
+    }
+
+    // Proves the claimed matching is a valid, complete solve: every claimed
+    // pair's underlying card values agree, and the claimed slot-A/slot-B
+    // indices together form a permutation of the board's 16 positions (so
+    // no position is left unmatched or reused). The verdict is revealed
+    // (not owner-encrypted) because the program must act on it directly
+    // to flip `round_state.completed`.
+    #[instruction]
+    pub fn verify_solution(input_ctxt: Enc<Shared, VerifySolutionInput>) -> u8 {
+        let input = input_ctxt.to_arcis();
+
+        let mut all_pairs_match: u8 = 1;
+        let mut combined_idx = [0u8; CARD_COUNT];
+        for k in 0..CARD_COUNT / 2 {
+            let a_idx = input.claimed_a_idx[k] as usize;
+            let b_idx = input.claimed_b_idx[k] as usize;
+            let pair_matches: u8 = if input.cards_a[a_idx] == input.cards_b[b_idx] { 1 } else { 0 };
+            all_pairs_match *= pair_matches;
+            combined_idx[2 * k] = input.claimed_a_idx[k];
+            combined_idx[2 * k + 1] = input.claimed_b_idx[k];
+        }
+
+        for &(i, j) in SHUFFLE_NETWORK.iter() {
+            let (lo, hi) = compare_exchange_u8(combined_idx[i], combined_idx[j]);
+            combined_idx[i] = lo;
+            combined_idx[j] = hi;
+        }
+
+        let mut is_permutation: u8 = 1;
+        for i in 0..CARD_COUNT {
+            let matches_position: u8 = if combined_idx[i] == i as u8 { 1 } else { 0 };
+            is_permutation *= matches_position;
+        }
+
+        let solved: u8 = all_pairs_match * is_permutation;
+        solved.reveal()
+    }
 }
@@ -1,10 +1,23 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::system_program::{transfer, Transfer};
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
 const COMP_DEF_OFFSET_VERIFY_PAIR: u32 = comp_def_offset("verify_pair");
+const COMP_DEF_OFFSET_SHUFFLE_DECK: u32 = comp_def_offset("shuffle_deck");
+const COMP_DEF_OFFSET_VERIFY_PAIRS: u32 = comp_def_offset("verify_pairs");
+const COMP_DEF_OFFSET_VERIFY_SOLUTION: u32 = comp_def_offset("verify_solution");
 const CARD_COUNT: usize = 16;
+const MAX_PAIR_BATCH: usize = 8;
 const ROUND_STATE_SEED: &[u8] = b"round_state";
+const GAME_CONFIG_SEED: &[u8] = b"game_config";
+const MATCH_SEED: &[u8] = b"match";
+const VAULT_SEED: &[u8] = b"vault";
+const MAX_PROTOCOL_FEE_BPS: u16 = 1_000;
 
 declare_id!("HSPR8gNS9VN8hVRhRiDAWDo17WmTzENCZAdQeNepG8oy");
 
@@ -12,6 +25,27 @@ declare_id!("HSPR8gNS9VN8hVRhRiDAWDo17WmTzENCZAdQeNepG8oy");
 pub mod blockchain {
     use super::*;
 
+    pub fn init_config(ctx: Context<InitConfig>, scorekeeper: [u8; 32]) -> Result<()> {
+        let config = &mut ctx.accounts.game_config;
+        config.authority = ctx.accounts.payer.key();
+        config.scorekeeper = scorekeeper;
+        config.bump = ctx.bumps.game_config;
+        Ok(())
+    }
+
+    pub fn set_authority(ctx: Context<SetAuthority>, scorekeeper: [u8; 32]) -> Result<()> {
+        ctx.accounts.game_config.scorekeeper = scorekeeper;
+        Ok(())
+    }
+
+    pub fn set_treasury(ctx: Context<SetAuthority>, treasury: Pubkey, protocol_fee_bps: u16) -> Result<()> {
+        require!(protocol_fee_bps <= MAX_PROTOCOL_FEE_BPS, ErrorCode::InvalidProtocolFee);
+        let config = &mut ctx.accounts.game_config;
+        config.treasury = treasury;
+        config.protocol_fee_bps = protocol_fee_bps;
+        Ok(())
+    }
+
     pub fn register_round(
         ctx: Context<RegisterRound>,
         round_id: u64,
@@ -36,6 +70,10 @@ pub mod blockchain {
         round_state.turns_used = 0;
         round_state.pairs_found = 0;
         round_state.completed = false;
+        round_state.solve_ms = 0;
+        round_state.dealt = false;
+        round_state.last_settle_nonce = [0u8; 32];
+        round_state.last_settle_seq = 0;
         round_state.bump = ctx.bumps.round_state;
         Ok(())
     }
@@ -61,6 +99,120 @@ pub mod blockchain {
         Ok(())
     }
 
+    pub fn init_shuffle_deck_comp_def(ctx: Context<InitShuffleDeckCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_verify_pairs_comp_def(ctx: Context<InitVerifyPairsCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn init_verify_solution_comp_def(ctx: Context<InitVerifySolutionCompDef>) -> Result<()> {
+        init_comp_def(ctx.accounts, None, None)?;
+        Ok(())
+    }
+
+    pub fn prove_solved(
+        ctx: Context<ProveSolved>,
+        round_id: u64,
+        claimed_a_idx: [u8; CARD_COUNT / 2],
+        claimed_b_idx: [u8; CARD_COUNT / 2],
+        computation_offset: u64,
+        _nonce: u128,
+    ) -> Result<()> {
+        require!(round_id == ctx.accounts.round_state.round_id, ErrorCode::RoundIdMismatch);
+        for idx in claimed_a_idx.iter().chain(claimed_b_idx.iter()) {
+            require!(*idx < CARD_COUNT as u8, ErrorCode::CardIndexOutOfBounds);
+        }
+
+        let board_nonce = u128::from_le_bytes(ctx.accounts.round_state.board_nonce);
+        let mut args_builder = ArgBuilder::new()
+            .x25519_pubkey(ctx.accounts.round_state.player_pubkey)
+            .plaintext_u128(board_nonce);
+        for cipher in ctx.accounts.round_state.encrypted_cards_slot_a.iter() {
+            args_builder = args_builder.encrypted_u8(*cipher);
+        }
+        for cipher in ctx.accounts.round_state.encrypted_cards_slot_b.iter() {
+            args_builder = args_builder.encrypted_u8(*cipher);
+        }
+        for idx in claimed_a_idx.iter() {
+            args_builder = args_builder.plaintext_u8(*idx);
+        }
+        for idx in claimed_b_idx.iter() {
+            args_builder = args_builder.plaintext_u8(*idx);
+        }
+        let args = args_builder.build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let callback_accounts = vec![CallbackAccount {
+            pubkey: ctx.accounts.round_state.key(),
+            is_writable: true,
+        }];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![ProveSolvedCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
+        Ok(())
+    }
+
+    pub fn deal_board(
+        ctx: Context<DealBoard>,
+        round_id: u64,
+        computation_offset: u64,
+        _nonce: u128,
+    ) -> Result<()> {
+        require!(round_id == ctx.accounts.round_state.round_id, ErrorCode::RoundIdMismatch);
+
+        // Shuffle the slot-A cards the player already committed to at
+        // register_round — never ciphertexts supplied at deal time, or the
+        // player could hand in a freshly chosen board. The callback writes
+        // the shuffled result into both slot A and slot B.
+        let board_nonce = u128::from_le_bytes(ctx.accounts.round_state.board_nonce);
+        let mut args_builder = ArgBuilder::new()
+            .x25519_pubkey(ctx.accounts.round_state.player_pubkey)
+            .plaintext_u128(board_nonce);
+        for card in ctx.accounts.round_state.encrypted_cards_slot_a.iter() {
+            args_builder = args_builder.encrypted_u8(*card);
+        }
+        let args = args_builder
+            .plaintext_u128(board_nonce)
+            .plaintext_u128(round_id as u128)
+            .build();
+
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let callback_accounts = vec![CallbackAccount {
+            pubkey: ctx.accounts.round_state.key(),
+            is_writable: true,
+        }];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![DealBoardCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
+        Ok(())
+    }
+
     pub fn verify_pair(
         ctx: Context<VerifyPair>,
         round_id: u64,
@@ -107,6 +259,73 @@ pub mod blockchain {
         Ok(())
     }
 
+    pub fn verify_pairs(
+        ctx: Context<VerifyPairs>,
+        round_id: u64,
+        pairs: Vec<(u8, u8)>,
+        computation_offset: u64,
+        _nonce: u128,
+    ) -> Result<()> {
+        require!(round_id == ctx.accounts.round_state.round_id, ErrorCode::RoundIdMismatch);
+        require!(
+            !pairs.is_empty() && pairs.len() <= MAX_PAIR_BATCH,
+            ErrorCode::InvalidBatchSize
+        );
+
+        let mut card_a_ciphers = [[0u8; 32]; MAX_PAIR_BATCH];
+        let mut card_b_ciphers = [[0u8; 32]; MAX_PAIR_BATCH];
+        for (slot, &(card_a_idx, card_b_idx)) in pairs.iter().enumerate() {
+            require!(card_a_idx < CARD_COUNT as u8, ErrorCode::CardIndexOutOfBounds);
+            require!(card_b_idx < CARD_COUNT as u8, ErrorCode::CardIndexOutOfBounds);
+            card_a_ciphers[slot] = ctx.accounts.round_state.encrypted_cards_slot_a[card_a_idx as usize];
+            card_b_ciphers[slot] = ctx.accounts.round_state.encrypted_cards_slot_b[card_b_idx as usize];
+        }
+        // Pad unused batch slots by repeating the first pair; the client
+        // ignores match results past `pairs.len()`.
+        for slot in pairs.len()..MAX_PAIR_BATCH {
+            card_a_ciphers[slot] = card_a_ciphers[0];
+            card_b_ciphers[slot] = card_b_ciphers[0];
+        }
+
+        let board_nonce = u128::from_le_bytes(ctx.accounts.round_state.board_nonce);
+        let mut args_builder = ArgBuilder::new()
+            .x25519_pubkey(ctx.accounts.round_state.player_pubkey)
+            .plaintext_u128(board_nonce);
+        for slot in 0..MAX_PAIR_BATCH {
+            args_builder = args_builder.encrypted_u8(card_a_ciphers[slot]);
+        }
+        for slot in 0..MAX_PAIR_BATCH {
+            args_builder = args_builder.encrypted_u8(card_b_ciphers[slot]);
+        }
+        let args = args_builder.build();
+
+        ctx.accounts.round_state.turns_used = ctx
+            .accounts
+            .round_state
+            .turns_used
+            .saturating_add(pairs.len() as u16);
+        ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+        let callback_accounts = vec![CallbackAccount {
+            pubkey: ctx.accounts.round_state.key(),
+            is_writable: true,
+        }];
+
+        queue_computation(
+            ctx.accounts,
+            computation_offset,
+            args,
+            vec![VerifyPairsCallback::callback_ix(
+                computation_offset,
+                &ctx.accounts.mxe_account,
+                &callback_accounts,
+            )?],
+            1,
+            0,
+        )?;
+        Ok(())
+    }
+
     #[arcium_callback(encrypted_ix = "verify_pair")]
     pub fn verify_pair_callback(
         ctx: Context<VerifyPairCallback>,
@@ -131,6 +350,89 @@ pub mod blockchain {
         Ok(())
     }
 
+    #[arcium_callback(encrypted_ix = "verify_pairs")]
+    pub fn verify_pairs_callback(
+        ctx: Context<VerifyPairsCallback>,
+        output: SignedComputationOutputs<VerifyPairsOutput>,
+    ) -> Result<()> {
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(VerifyPairsOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let mut match_ciphers = [[0u8; 32]; MAX_PAIR_BATCH];
+        for (idx, cipher) in o.ciphertexts.iter().take(MAX_PAIR_BATCH).enumerate() {
+            match_ciphers[idx] = *cipher;
+        }
+
+        emit!(PairsVerified {
+            player: ctx.accounts.round_state.player,
+            round_id: ctx.accounts.round_state.round_id,
+            turns_used: ctx.accounts.round_state.turns_used,
+            pairs_found: ctx.accounts.round_state.pairs_found,
+            match_ciphers,
+            nonce: o.nonce.to_le_bytes(),
+        });
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "verify_solution")]
+    pub fn prove_solved_callback(
+        ctx: Context<ProveSolvedCallback>,
+        output: SignedComputationOutputs<VerifySolutionOutput>,
+    ) -> Result<()> {
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(VerifySolutionOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        if o == 1 {
+            ctx.accounts.round_state.completed = true;
+        }
+
+        emit!(SolutionProven {
+            player: ctx.accounts.round_state.player,
+            round_id: ctx.accounts.round_state.round_id,
+            completed: ctx.accounts.round_state.completed,
+        });
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "shuffle_deck")]
+    pub fn deal_board_callback(
+        ctx: Context<DealBoardCallback>,
+        output: SignedComputationOutputs<ShuffleDeckOutput>,
+    ) -> Result<()> {
+        let o = match output.verify_output(
+            &ctx.accounts.cluster_account,
+            &ctx.accounts.computation_account,
+        ) {
+            Ok(ShuffleDeckOutput { field_0 }) => field_0,
+            Err(_) => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        let mut shuffled = [[0u8; 32]; CARD_COUNT];
+        for (idx, cipher) in o.ciphertexts.iter().take(CARD_COUNT).enumerate() {
+            shuffled[idx] = *cipher;
+        }
+        ctx.accounts.round_state.encrypted_cards_slot_a = shuffled;
+        ctx.accounts.round_state.encrypted_cards_slot_b = shuffled;
+        ctx.accounts.round_state.dealt = true;
+
+        emit!(BoardDealt {
+            player: ctx.accounts.round_state.player,
+            round_id: ctx.accounts.round_state.round_id,
+            nonce: o.nonce.to_le_bytes(),
+        });
+        Ok(())
+    }
+
     pub fn settle_round_score(
         ctx: Context<SettleRoundScore>,
         round_id: u64,
@@ -140,17 +442,54 @@ pub mod blockchain {
         solve_ms: u64,
         points_delta: i64,
         nonce_hash: [u8; 32],
+        settle_seq: u64,
     ) -> Result<()> {
         require!(round_id == ctx.accounts.round_state.round_id, ErrorCode::RoundIdMismatch);
         require!(
             ctx.accounts.round_state.player == ctx.accounts.payer.key(),
             ErrorCode::UnauthorizedRoundOwner
         );
+        // Rejecting only the exact previous nonce would still let a stale
+        // but more favorable earlier attestation be replayed after a later,
+        // truthful one landed. `settle_seq` is a scorekeeper-assigned counter
+        // that must strictly increase, so no earlier attestation can ever be
+        // re-applied once a newer one has settled.
+        require!(
+            settle_seq > ctx.accounts.round_state.last_settle_seq,
+            ErrorCode::StaleSettleSequence
+        );
+        require!(
+            nonce_hash != ctx.accounts.round_state.last_settle_nonce,
+            ErrorCode::NonceAlreadyUsed
+        );
+
+        let mut message = Vec::with_capacity(8 + 2 + 1 + 1 + 8 + 8 + 32 + 8);
+        message.extend_from_slice(&round_id.to_le_bytes());
+        message.extend_from_slice(&turns_used.to_le_bytes());
+        message.extend_from_slice(&pairs_found.to_le_bytes());
+        message.push(completed as u8);
+        message.extend_from_slice(&solve_ms.to_le_bytes());
+        message.extend_from_slice(&points_delta.to_le_bytes());
+        message.extend_from_slice(&nonce_hash);
+        message.extend_from_slice(&settle_seq.to_le_bytes());
+        let message_hash = anchor_lang::solana_program::hash::hash(&message);
+
+        verify_score_signature(
+            &ctx.accounts.instructions_sysvar,
+            &ctx.accounts.game_config.scorekeeper,
+            message_hash.as_ref(),
+        )?;
 
+        // `completed` is attested here for scorekeeper record-keeping only;
+        // round_state.completed itself is set exclusively by the
+        // verify_solution MPC proof in `prove_solved_callback`, so it can
+        // never be asserted by a client or scorekeeper signature alone.
         let round_state = &mut ctx.accounts.round_state;
         round_state.turns_used = turns_used;
         round_state.pairs_found = pairs_found;
-        round_state.completed = completed;
+        round_state.solve_ms = solve_ms;
+        round_state.last_settle_nonce = nonce_hash;
+        round_state.last_settle_seq = settle_seq;
 
         emit!(RoundSettled {
             player: ctx.accounts.payer.key(),
@@ -161,6 +500,267 @@ pub mod blockchain {
             solve_ms,
             points_delta,
             nonce_hash,
+            settle_seq,
+        });
+        Ok(())
+    }
+
+    pub fn create_match(
+        ctx: Context<CreateMatch>,
+        round_id: u64,
+        stake_lamports: u64,
+        deadline_slot: u64,
+    ) -> Result<()> {
+        require!(stake_lamports > 0, ErrorCode::InvalidStake);
+
+        // The board commitment is derived from the creator's own registered
+        // round, never taken as a bare client argument, so it ties the match
+        // to the board_nonce that round was registered with.
+        let board_commitment = board_commitment_hash(&ctx.accounts.creator_round_state.board_nonce);
+
+        let match_account = &mut ctx.accounts.match_account;
+        match_account.round_id = round_id;
+        match_account.board_commitment = board_commitment;
+        match_account.creator = ctx.accounts.creator.key();
+        match_account.opponent = Pubkey::default();
+        match_account.stake_lamports = stake_lamports;
+        match_account.state = MatchState::Open;
+        match_account.deadline_slot = deadline_slot;
+        // Set for real once the opponent joins and proposes a play window;
+        // MatchState::Open guards settle_match from relying on this early.
+        match_account.play_deadline_slot = 0;
+        match_account.bump = ctx.bumps.match_account;
+
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            stake_lamports,
+        )?;
+        Ok(())
+    }
+
+    pub fn join_match(ctx: Context<JoinMatch>, round_id: u64, play_deadline_slot: u64) -> Result<()> {
+        require!(round_id == ctx.accounts.match_account.round_id, ErrorCode::RoundIdMismatch);
+        require!(ctx.accounts.match_account.state == MatchState::Open, ErrorCode::MatchNotOpen);
+        require!(
+            Clock::get()?.slot <= ctx.accounts.match_account.deadline_slot,
+            ErrorCode::DeadlinePassed
+        );
+        require!(
+            play_deadline_slot > Clock::get()?.slot,
+            ErrorCode::InvalidPlayDeadline
+        );
+        require!(
+            board_commitment_hash(&ctx.accounts.opponent_round_state.board_nonce)
+                == ctx.accounts.match_account.board_commitment,
+            ErrorCode::BoardCommitmentMismatch
+        );
+
+        let stake_lamports = ctx.accounts.match_account.stake_lamports;
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.opponent.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            stake_lamports,
+        )?;
+
+        let match_account = &mut ctx.accounts.match_account;
+        match_account.opponent = ctx.accounts.opponent.key();
+        match_account.state = MatchState::Joined;
+        match_account.play_deadline_slot = play_deadline_slot;
+        Ok(())
+    }
+
+    pub fn settle_match(ctx: Context<SettleMatch>, round_id: u64) -> Result<()> {
+        require!(round_id == ctx.accounts.match_account.round_id, ErrorCode::RoundIdMismatch);
+        require!(
+            ctx.accounts.match_account.state != MatchState::Settled,
+            ErrorCode::MatchAlreadySettled
+        );
+
+        let match_key = ctx.accounts.match_account.key();
+        let vault_bump = ctx.bumps.vault;
+        let vault_seeds: &[&[u8]] = &[VAULT_SEED, match_key.as_ref(), &[vault_bump]];
+        let vault_lamports = ctx.accounts.vault.lamports();
+
+        if ctx.accounts.match_account.state == MatchState::Open {
+            require!(
+                Clock::get()?.slot > ctx.accounts.match_account.deadline_slot,
+                ErrorCode::DeadlineNotPassed
+            );
+
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.creator.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                vault_lamports,
+            )?;
+
+            ctx.accounts.match_account.state = MatchState::Settled;
+
+            emit!(MatchSettled {
+                round_id,
+                winner: None,
+                payout_lamports: vault_lamports,
+                protocol_fee_lamports: 0,
+                refunded: true,
+            });
+            return Ok(());
+        }
+
+        let (expected_creator_round, _) = Pubkey::find_program_address(
+            &[ROUND_STATE_SEED, ctx.accounts.creator.key().as_ref(), &round_id.to_le_bytes()],
+            ctx.program_id,
+        );
+        let (expected_opponent_round, _) = Pubkey::find_program_address(
+            &[ROUND_STATE_SEED, ctx.accounts.opponent.key().as_ref(), &round_id.to_le_bytes()],
+            ctx.program_id,
+        );
+        require!(
+            ctx.accounts.creator_round_state.key() == expected_creator_round
+                && ctx.accounts.opponent_round_state.key() == expected_opponent_round,
+            ErrorCode::RoundIdMismatch
+        );
+
+        let creator_round_state =
+            Account::<RoundState>::try_from(&ctx.accounts.creator_round_state.to_account_info())?;
+        let opponent_round_state =
+            Account::<RoundState>::try_from(&ctx.accounts.opponent_round_state.to_account_info())?;
+
+        // Re-check against the board committed to at create_match time, not
+        // just at join time, since round_state can be rewritten up until
+        // the round is settled.
+        let board_commitment = ctx.accounts.match_account.board_commitment;
+        require!(
+            board_commitment_hash(&creator_round_state.board_nonce) == board_commitment
+                && board_commitment_hash(&opponent_round_state.board_nonce) == board_commitment,
+            ErrorCode::BoardCommitmentMismatch
+        );
+
+        // board_commitment only attests to board_nonce, not the post-shuffle
+        // ciphertext content, so without this both players' boards could
+        // still be in their raw, self-dealt (un-shuffled) state.
+        require!(
+            creator_round_state.dealt && opponent_round_state.dealt,
+            ErrorCode::RoundNotDealt
+        );
+
+        let both_completed = creator_round_state.completed && opponent_round_state.completed;
+        if !both_completed {
+            require!(
+                Clock::get()?.slot > ctx.accounts.match_account.play_deadline_slot,
+                ErrorCode::RoundNotCompleted
+            );
+
+            // Neither (or only one) player proved completion within the play
+            // window agreed to at join_match time -- split the stake back
+            // evenly instead of leaving it stuck in the vault forever.
+            let creator_refund = vault_lamports / 2;
+            let opponent_refund = vault_lamports - creator_refund;
+
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.creator.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                creator_refund,
+            )?;
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.opponent.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                opponent_refund,
+            )?;
+
+            ctx.accounts.match_account.state = MatchState::Settled;
+
+            emit!(MatchSettled {
+                round_id,
+                winner: None,
+                payout_lamports: vault_lamports,
+                protocol_fee_lamports: 0,
+                refunded: true,
+            });
+            return Ok(());
+        }
+
+        let creator_wins = creator_round_state.turns_used < opponent_round_state.turns_used
+            || (creator_round_state.turns_used == opponent_round_state.turns_used
+                && creator_round_state.solve_ms <= opponent_round_state.solve_ms);
+
+        let winner = if creator_wins {
+            ctx.accounts.creator.key()
+        } else {
+            ctx.accounts.opponent.key()
+        };
+
+        let protocol_fee_lamports = vault_lamports
+            .saturating_mul(ctx.accounts.game_config.protocol_fee_bps as u64)
+            / 10_000;
+        let payout_lamports = vault_lamports.saturating_sub(protocol_fee_lamports);
+
+        if protocol_fee_lamports > 0 {
+            transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                protocol_fee_lamports,
+            )?;
+        }
+
+        let winner_account = if creator_wins {
+            ctx.accounts.creator.to_account_info()
+        } else {
+            ctx.accounts.opponent.to_account_info()
+        };
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: winner_account,
+                },
+                &[vault_seeds],
+            ),
+            payout_lamports,
+        )?;
+
+        ctx.accounts.match_account.state = MatchState::Settled;
+
+        emit!(MatchSettled {
+            round_id,
+            winner: Some(winner),
+            payout_lamports,
+            protocol_fee_lamports,
+            refunded: false,
         });
         Ok(())
     }
@@ -177,6 +777,7 @@ pub struct VerifyPair<'info> {
         seeds = [ROUND_STATE_SEED, payer.key().as_ref(), &round_id.to_le_bytes()],
         bump = round_state.bump,
         constraint = round_state.player == payer.key() @ ErrorCode::UnauthorizedRoundOwner,
+        constraint = round_state.dealt @ ErrorCode::RoundNotDealt,
     )]
     pub round_state: Box<Account<'info, RoundState>>,
     #[account(
@@ -242,17 +843,316 @@ pub struct VerifyPairCallback<'info> {
     pub round_state: Box<Account<'info, RoundState>>,
 }
 
-#[init_computation_definition_accounts("verify_pair", payer)]
+#[queue_computation_accounts("verify_pairs", payer)]
 #[derive(Accounts)]
-pub struct InitVerifyPairCompDef<'info> {
+#[instruction(round_id: u64, pairs: Vec<(u8, u8)>, computation_offset: u64, nonce: u128)]
+pub struct VerifyPairs<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     #[account(
         mut,
-        address = derive_mxe_pda!()
+        seeds = [ROUND_STATE_SEED, payer.key().as_ref(), &round_id.to_le_bytes()],
+        bump = round_state.bump,
+        constraint = round_state.player == payer.key() @ ErrorCode::UnauthorizedRoundOwner,
+        constraint = round_state.dealt @ ErrorCode::RoundNotDealt,
     )]
-    pub mxe_account: Box<Account<'info, MXEAccount>>,
-    #[account(mut)]
+    pub round_state: Box<Account<'info, RoundState>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_PAIRS))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("verify_pairs")]
+#[derive(Accounts)]
+pub struct VerifyPairsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_PAIRS))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: checked by arcium program via callback constraints.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut)]
+    pub round_state: Box<Account<'info, RoundState>>,
+}
+
+#[init_computation_definition_accounts("verify_pairs", payer)]
+#[derive(Accounts)]
+pub struct InitVerifyPairsCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot)
+    )]
+    /// CHECK: checked by arcium program
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: LUT program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("verify_solution", payer)]
+#[derive(Accounts)]
+#[instruction(round_id: u64, claimed_a_idx: [u8; CARD_COUNT / 2], claimed_b_idx: [u8; CARD_COUNT / 2], computation_offset: u64, nonce: u128)]
+pub struct ProveSolved<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ROUND_STATE_SEED, payer.key().as_ref(), &round_id.to_le_bytes()],
+        bump = round_state.bump,
+        constraint = round_state.player == payer.key() @ ErrorCode::UnauthorizedRoundOwner,
+        constraint = round_state.dealt @ ErrorCode::RoundNotDealt,
+    )]
+    pub round_state: Box<Account<'info, RoundState>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_SOLUTION))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("verify_solution")]
+#[derive(Accounts)]
+pub struct ProveSolvedCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_VERIFY_SOLUTION))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: checked by arcium program via callback constraints.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut)]
+    pub round_state: Box<Account<'info, RoundState>>,
+}
+
+#[init_computation_definition_accounts("verify_solution", payer)]
+#[derive(Accounts)]
+pub struct InitVerifySolutionCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot)
+    )]
+    /// CHECK: checked by arcium program
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: LUT program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[init_computation_definition_accounts("verify_pair", payer)]
+#[derive(Accounts)]
+pub struct InitVerifyPairCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: checked by arcium program
+    pub comp_def_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_lut_pda!(mxe_account.lut_offset_slot)
+    )]
+    /// CHECK: checked by arcium program
+    pub address_lookup_table: UncheckedAccount<'info>,
+    #[account(address = LUT_PROGRAM_ID)]
+    /// CHECK: LUT program
+    pub lut_program: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("shuffle_deck", payer)]
+#[derive(Accounts)]
+#[instruction(round_id: u64, computation_offset: u64, nonce: u128)]
+pub struct DealBoard<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [ROUND_STATE_SEED, payer.key().as_ref(), &round_id.to_le_bytes()],
+        bump = round_state.bump,
+        constraint = round_state.player == payer.key() @ ErrorCode::UnauthorizedRoundOwner,
+    )]
+    pub round_state: Box<Account<'info, RoundState>>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, ArciumSignerAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: checked by the arcium program.
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: checked by the arcium program.
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset, mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    /// CHECK: checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SHUFFLE_DECK))]
+    pub comp_def_account: Box<Account<'info, ComputationDefinitionAccount>>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet)
+    )]
+    pub cluster_account: Box<Account<'info, Cluster>>,
+    #[account(mut, address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS)]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(mut, address = ARCIUM_CLOCK_ACCOUNT_ADDRESS)]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+}
+
+#[callback_accounts("shuffle_deck")]
+#[derive(Accounts)]
+pub struct DealBoardCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(address = derive_comp_def_pda!(COMP_DEF_OFFSET_SHUFFLE_DECK))]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = derive_mxe_pda!())]
+    pub mxe_account: Account<'info, MXEAccount>,
+    /// CHECK: checked by arcium program via callback constraints.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(address = derive_cluster_pda!(mxe_account, ErrorCode::ClusterNotSet))]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(mut)]
+    pub round_state: Box<Account<'info, RoundState>>,
+}
+
+#[init_computation_definition_accounts("shuffle_deck", payer)]
+#[derive(Accounts)]
+pub struct InitShuffleDeckCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
     /// CHECK: checked by arcium program
     pub comp_def_account: UncheckedAccount<'info>,
     #[account(
@@ -296,6 +1196,138 @@ pub struct SettleRoundScore<'info> {
         constraint = round_state.player == payer.key() @ ErrorCode::UnauthorizedRoundOwner,
     )]
     pub round_state: Box<Account<'info, RoundState>>,
+    #[account(seeds = [GAME_CONFIG_SEED], bump = game_config.bump)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = GameConfig::SPACE,
+        seeds = [GAME_CONFIG_SEED],
+        bump,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuthority<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [GAME_CONFIG_SEED],
+        bump = game_config.bump,
+        constraint = game_config.authority == authority.key() @ ErrorCode::UnauthorizedConfigAuthority,
+    )]
+    pub game_config: Account<'info, GameConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct CreateMatch<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        seeds = [ROUND_STATE_SEED, creator.key().as_ref(), &round_id.to_le_bytes()],
+        bump = creator_round_state.bump,
+        constraint = creator_round_state.player == creator.key() @ ErrorCode::UnauthorizedRoundOwner,
+        constraint = creator_round_state.dealt @ ErrorCode::RoundNotDealt,
+    )]
+    pub creator_round_state: Box<Account<'info, RoundState>>,
+    #[account(
+        init,
+        payer = creator,
+        space = Match::SPACE,
+        seeds = [MATCH_SEED, creator.key().as_ref(), &round_id.to_le_bytes()],
+        bump,
+    )]
+    pub match_account: Account<'info, Match>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, match_account.key().as_ref()],
+        bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct JoinMatch<'info> {
+    #[account(mut)]
+    pub opponent: Signer<'info>,
+    /// CHECK: only read to derive the match PDA seeds.
+    pub creator: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [MATCH_SEED, creator.key().as_ref(), &round_id.to_le_bytes()],
+        bump = match_account.bump,
+        constraint = match_account.creator == creator.key() @ ErrorCode::UnauthorizedRoundOwner,
+    )]
+    pub match_account: Account<'info, Match>,
+    #[account(
+        seeds = [ROUND_STATE_SEED, opponent.key().as_ref(), &round_id.to_le_bytes()],
+        bump = opponent_round_state.bump,
+        constraint = opponent_round_state.player == opponent.key() @ ErrorCode::UnauthorizedRoundOwner,
+        constraint = opponent_round_state.dealt @ ErrorCode::RoundNotDealt,
+    )]
+    pub opponent_round_state: Box<Account<'info, RoundState>>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, match_account.key().as_ref()],
+        bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(round_id: u64)]
+pub struct SettleMatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(mut)]
+    /// CHECK: receives the refund or stake payout; address checked via match_account.creator.
+    pub creator: UncheckedAccount<'info>,
+    #[account(mut)]
+    /// CHECK: receives the stake payout; address checked via match_account.opponent.
+    pub opponent: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [MATCH_SEED, creator.key().as_ref(), &round_id.to_le_bytes()],
+        bump = match_account.bump,
+        constraint = match_account.creator == creator.key() @ ErrorCode::UnauthorizedRoundOwner,
+        constraint = match_account.state == MatchState::Open
+            || match_account.opponent == opponent.key() @ ErrorCode::UnauthorizedRoundOwner,
+    )]
+    pub match_account: Account<'info, Match>,
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, match_account.key().as_ref()],
+        bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    /// CHECK: deserialized and address-checked in the handler, since it is
+    /// only required when the match reached `MatchState::Joined`.
+    pub creator_round_state: UncheckedAccount<'info>,
+    /// CHECK: deserialized and address-checked in the handler, since it is
+    /// only required when the match reached `MatchState::Joined`.
+    pub opponent_round_state: UncheckedAccount<'info>,
+    #[account(seeds = [GAME_CONFIG_SEED], bump = game_config.bump)]
+    pub game_config: Box<Account<'info, GameConfig>>,
+    #[account(mut, address = game_config.treasury)]
+    /// CHECK: protocol fee recipient, verified against game_config.treasury.
+    pub treasury: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -323,11 +1355,120 @@ pub struct RoundState {
     pub turns_used: u16,
     pub pairs_found: u8,
     pub completed: bool,
+    pub solve_ms: u64,
+    pub dealt: bool,
+    pub last_settle_nonce: [u8; 32],
+    pub last_settle_seq: u64,
     pub bump: u8,
 }
 
 impl RoundState {
-    pub const SPACE: usize = 8 + 32 + 8 + (32 * CARD_COUNT) + (32 * CARD_COUNT) + 32 + 16 + 2 + 1 + 1 + 1;
+    pub const SPACE: usize = 8
+        + 32
+        + 8
+        + (32 * CARD_COUNT)
+        + (32 * CARD_COUNT)
+        + 32
+        + 16
+        + 2
+        + 1
+        + 1
+        + 8
+        + 1
+        + 32
+        + 8
+        + 1;
+}
+
+#[account]
+pub struct Match {
+    pub round_id: u64,
+    pub board_commitment: [u8; 32],
+    pub creator: Pubkey,
+    pub opponent: Pubkey,
+    pub stake_lamports: u64,
+    pub state: MatchState,
+    pub deadline_slot: u64,
+    pub play_deadline_slot: u64,
+    pub bump: u8,
+}
+
+impl Match {
+    pub const SPACE: usize = 8 + 8 + 32 + 32 + 32 + 8 + 1 + 8 + 8 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum MatchState {
+    Open,
+    Joined,
+    Settled,
+}
+
+#[account]
+pub struct GameConfig {
+    pub authority: Pubkey,
+    pub scorekeeper: [u8; 32],
+    pub treasury: Pubkey,
+    pub protocol_fee_bps: u16,
+    pub bump: u8,
+}
+
+impl GameConfig {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 2 + 1;
+}
+
+/// Commits to the `board_nonce` a `Match` was created against, so
+/// `join_match`/`settle_match` can cross-check that both players' rounds
+/// were registered with the same `board_nonce`. The ciphertexts of the
+/// board are not part of the commitment.
+fn board_commitment_hash(board_nonce: &[u8; 16]) -> [u8; 32] {
+    anchor_lang::solana_program::hash::hash(board_nonce).to_bytes()
+}
+
+/// Verifies that the instruction immediately preceding this one in the
+/// transaction is a Solana Ed25519-program instruction signed by
+/// `expected_signer` over `expected_message`.
+fn verify_score_signature(
+    instructions_sysvar: &AccountInfo,
+    expected_signer: &[u8; 32],
+    expected_message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require!(current_index > 0, ErrorCode::InvalidScoreSignature);
+
+    let ed25519_ix = load_instruction_at_checked((current_index - 1) as usize, instructions_sysvar)?;
+    require!(
+        ed25519_ix.program_id == ed25519_program::ID,
+        ErrorCode::InvalidScoreSignature
+    );
+
+    let data = &ed25519_ix.data;
+    require!(data.len() >= 16, ErrorCode::InvalidScoreSignature);
+    require!(data[0] == 1, ErrorCode::InvalidScoreSignature);
+
+    let offsets = &data[2..16];
+    let public_key_offset = u16::from_le_bytes([offsets[4], offsets[5]]) as usize;
+    let public_key_instruction_index = i16::from_le_bytes([offsets[6], offsets[7]]);
+    let message_data_offset = u16::from_le_bytes([offsets[8], offsets[9]]) as usize;
+    let message_data_size = u16::from_le_bytes([offsets[10], offsets[11]]) as usize;
+    let message_instruction_index = i16::from_le_bytes([offsets[12], offsets[13]]);
+
+    require!(
+        public_key_instruction_index == -1 && message_instruction_index == -1,
+        ErrorCode::InvalidScoreSignature
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(ErrorCode::InvalidScoreSignature)?;
+    require!(public_key == expected_signer, ErrorCode::InvalidScoreSignature);
+
+    let message = data
+        .get(message_data_offset..message_data_offset + message_data_size)
+        .ok_or(ErrorCode::InvalidScoreSignature)?;
+    require!(message == expected_message, ErrorCode::InvalidScoreSignature);
+
+    Ok(())
 }
 
 #[event]
@@ -340,6 +1481,39 @@ pub struct PairVerified {
     pub nonce: [u8; 16],
 }
 
+#[event]
+pub struct SolutionProven {
+    pub player: Pubkey,
+    pub round_id: u64,
+    pub completed: bool,
+}
+
+#[event]
+pub struct PairsVerified {
+    pub player: Pubkey,
+    pub round_id: u64,
+    pub turns_used: u16,
+    pub pairs_found: u8,
+    pub match_ciphers: [[u8; 32]; MAX_PAIR_BATCH],
+    pub nonce: [u8; 16],
+}
+
+#[event]
+pub struct BoardDealt {
+    pub player: Pubkey,
+    pub round_id: u64,
+    pub nonce: [u8; 16],
+}
+
+#[event]
+pub struct MatchSettled {
+    pub round_id: u64,
+    pub winner: Option<Pubkey>,
+    pub payout_lamports: u64,
+    pub protocol_fee_lamports: u64,
+    pub refunded: bool,
+}
+
 #[event]
 pub struct RoundSettled {
     pub player: Pubkey,
@@ -350,6 +1524,7 @@ pub struct RoundSettled {
     pub solve_ms: u64,
     pub points_delta: i64,
     pub nonce_hash: [u8; 32],
+    pub settle_seq: u64,
 }
 
 #[error_code]
@@ -366,4 +1541,34 @@ pub enum ErrorCode {
     UnauthorizedRoundOwner,
     #[msg("Round id mismatch")]
     RoundIdMismatch,
+    #[msg("Signer does not match the configured game authority")]
+    UnauthorizedConfigAuthority,
+    #[msg("Missing or invalid scorekeeper Ed25519 attestation")]
+    InvalidScoreSignature,
+    #[msg("Protocol fee exceeds the maximum allowed")]
+    InvalidProtocolFee,
+    #[msg("Stake must be greater than zero")]
+    InvalidStake,
+    #[msg("Match is not open for joining")]
+    MatchNotOpen,
+    #[msg("Match join deadline has passed")]
+    DeadlinePassed,
+    #[msg("Match join deadline has not passed yet")]
+    DeadlineNotPassed,
+    #[msg("Match has already been settled")]
+    MatchAlreadySettled,
+    #[msg("Both rounds must be completed before settling a match")]
+    RoundNotCompleted,
+    #[msg("Batch must contain between 1 and MAX_PAIR_BATCH pairs")]
+    InvalidBatchSize,
+    #[msg("Round does not match the board committed to at create_match")]
+    BoardCommitmentMismatch,
+    #[msg("deal_board must be called before this round can be played")]
+    RoundNotDealt,
+    #[msg("This score attestation's nonce has already been settled")]
+    NonceAlreadyUsed,
+    #[msg("play_deadline_slot must be in the future")]
+    InvalidPlayDeadline,
+    #[msg("settle_seq must strictly increase over the round's previous settlement")]
+    StaleSettleSequence,
 }